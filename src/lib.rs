@@ -1,23 +1,129 @@
-use std::{io::Read, io::Write};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
-use byteorder::{LE, ReadBytesExt, WriteBytesExt};
+use byteorder::{ByteOrder, LE};
 
-pub trait Readable<E = std::io::Error>
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E>
+/// Byte sink that [`Writeable`]/[`WriteExt`] are bounded on instead of
+/// `std::io::Write`, so it can be implemented over any byte sink without
+/// `std::io`. The crate itself is not `no_std`: it links `std` even with the
+/// `std` feature off, which only drops the blanket `std::io` impls and
+/// [`ser_to_vec`].
+pub trait Writer {
+    type Error;
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
+}
+/// Byte source that [`Readable`]/[`ReadExt`] are bounded on instead of
+/// `std::io::Read`. See [`Writer`].
+pub trait Reader {
+    type Error;
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+#[cfg(feature = "std")]
+mod io {
+    use super::{Reader, Writer};
+
+    impl<T: std::io::Read> Reader for T {
+        type Error = std::io::Error;
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+            std::io::Read::read_exact(self, buf)
+        }
+    }
+    impl<T: std::io::Write> Writer for T {
+        type Error = std::io::Error;
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+            std::io::Write::write_all(self, buf)
+        }
+    }
+}
+
+#[cfg(test)]
+mod io_tests {
+    use super::*;
+
+    #[test]
+    fn std_blanket_impls_satisfy_reader_and_writer() {
+        let mut sink: Vec<u8> = Vec::new();
+        Writer::write_all(&mut sink, &[1, 2, 3]).unwrap();
+        let mut cur = std::io::Cursor::new(sink);
+        let mut out = [0u8; 3];
+        Reader::read_exact(&mut cur, &mut out).unwrap();
+        assert_eq!(out, [1, 2, 3]);
+    }
+
+    /// `Reader`/`Writer` pair with no dependency on `std::io`.
+    struct ManualWriter(Vec<u8>);
+    impl Writer for ManualWriter {
+        type Error = std::convert::Infallible;
+        fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+            self.0.extend_from_slice(buf);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug)]
+    struct ManualEof;
+    struct ManualReader<'a> {
+        buf: &'a [u8],
+        pos: usize,
+    }
+    impl<'a> Reader for ManualReader<'a> {
+        type Error = ManualEof;
+        fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Self::Error> {
+            let end = self.pos + buf.len();
+            if end > self.buf.len() {
+                return Err(ManualEof);
+            }
+            buf.copy_from_slice(&self.buf[self.pos..end]);
+            self.pos = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn manual_writer_without_std_io_encodes_correctly() {
+        let mut sink = ManualWriter(Vec::new());
+        <u32 as Writeable<std::convert::Infallible>>::ser(&0xdead_beefu32, &mut sink).unwrap();
+        assert_eq!(sink.0, 0xdead_beefu32.to_le_bytes());
+    }
+
+    #[test]
+    fn manual_reader_without_std_io_decodes_correctly() {
+        let bytes = 0xdead_beefu32.to_le_bytes();
+        let mut src = ManualReader {
+            buf: &bytes,
+            pos: 0,
+        };
+        let value = <u32 as Readable<ManualEof>>::de(&mut src).unwrap();
+        assert_eq!(value, 0xdead_beefu32);
+    }
+
+    #[test]
+    fn manual_reader_reports_eof_on_truncated_input() {
+        let bytes = [0u8; 2];
+        let mut src = ManualReader {
+            buf: &bytes,
+            pos: 0,
+        };
+        assert!(<u32 as Readable<ManualEof>>::de(&mut src).is_err());
+    }
+}
+
+pub trait Readable<E = std::io::Error> {
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
     where
-        Self: Sized;
-    fn de_vec<S: Read>(len: usize, stream: &mut S) -> Result<Vec<Self>, E>
+        Self: Sized,
+        E: From<S::Error>;
+    fn de_vec<S: Reader>(len: usize, stream: &mut S) -> Result<Vec<Self>, E>
     where
         Self: Sized,
+        E: From<S::Error>,
     {
         read_array(len, stream, Self::de)
     }
-    fn de_array<S: Read, const N: usize>(stream: &mut S) -> Result<[Self; N], E>
+    fn de_array<S: Reader, const N: usize>(stream: &mut S) -> Result<[Self; N], E>
     where
         Self: Sized + Copy + Default,
+        E: From<S::Error>,
     {
         let mut buf = [Default::default(); N];
         for i in buf.iter_mut() {
@@ -26,307 +132,822 @@ where
         Ok(buf)
     }
 }
-pub trait Writeable<E = std::io::Error>
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E>;
-    fn ser_array<S: Write, T: AsRef<[Self]>>(this: T, stream: &mut S) -> Result<(), E>
+pub trait Writeable<E = std::io::Error> {
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>;
+    fn ser_array<S: Writer, T: AsRef<[Self]>>(this: T, stream: &mut S) -> Result<(), E>
     where
         Self: Sized,
+        E: From<S::Error>,
     {
         for i in this.as_ref() {
             Self::ser(i, stream)?;
         }
         Ok(())
     }
+    /// Rough estimate of `ser`'s output size in bytes, for pre-reserving a
+    /// buffer. Defaults to 0.
+    fn size_hint(&self) -> usize {
+        0
+    }
 }
-pub trait ReadableCtx<C, E = std::io::Error>
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S, ctx: C) -> Result<Self, E>
+pub trait ReadableCtx<C, E = std::io::Error> {
+    fn de<S: Reader>(stream: &mut S, ctx: C) -> Result<Self, E>
     where
-        Self: Sized;
+        Self: Sized,
+        E: From<S::Error>;
+}
+pub trait WriteableCtx<C, E = std::io::Error> {
+    fn ser<S: Writer>(&self, stream: &mut S, ctx: C) -> Result<(), E>
+    where
+        E: From<S::Error>;
 }
 
-impl<T> ReadExt for T where T: Read {}
-pub trait ReadExt: Read {
+impl<T> ReadExt for T where T: Reader {}
+pub trait ReadExt: Reader + Sized {
     fn de<T: Readable<E>, E>(&mut self) -> Result<T, E>
     where
-        Self: Sized,
-        E: From<std::io::Error>,
+        E: From<Self::Error>,
     {
         T::de(self)
     }
     fn de_ctx<T: ReadableCtx<C, E>, C, E>(&mut self, ctx: C) -> Result<T, E>
     where
-        Self: Sized,
-        E: From<std::io::Error>,
+        E: From<Self::Error>,
     {
         T::de(self, ctx)
     }
 }
-impl<T> WriteExt for T where T: Write {}
-pub trait WriteExt: Write {
+impl<T> WriteExt for T where T: Writer {}
+pub trait WriteExt: Writer + Sized {
     fn ser<T: Writeable<E>, E>(&mut self, value: &T) -> Result<(), E>
     where
-        Self: Sized,
-        E: From<std::io::Error>,
+        E: From<Self::Error>,
     {
         value.ser(self)
     }
+    fn ser_ctx<T: WriteableCtx<C, E>, C, E>(&mut self, value: &T, ctx: C) -> Result<(), E>
+    where
+        E: From<Self::Error>,
+    {
+        value.ser(self, ctx)
+    }
     /// Serialize &[T] without length prefix
     fn ser_no_length<T: Writeable<E>, S: AsRef<[T]>, E>(&mut self, value: &S) -> Result<(), E>
     where
-        Self: Sized,
-        E: From<std::io::Error>,
+        E: From<Self::Error>,
     {
         T::ser_array(value.as_ref(), self)
     }
+    /// Write `value`'s serialized size as a `u32` prefix, then `value` itself
+    fn ser_with_byte_prefix<T: Writeable<E>, E>(&mut self, value: &T) -> Result<(), E>
+    where
+        E: From<Self::Error>,
+    {
+        let size = serialized_size::<T, E>(value)? as u32;
+        <u32 as Writeable<E>>::ser(&size, self)?;
+        value.ser(self)
+    }
 }
 
-impl<const N: usize, T: Readable<E> + Default + Copy, E> Readable<E> for [T; N]
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E> {
+/// Serialize `value` into a freshly allocated `Vec<u8>`, pre-reserved using
+/// [`Writeable::size_hint`]
+#[cfg(feature = "std")]
+pub fn ser_to_vec<T: Writeable<E>, E: From<std::io::Error>>(value: &T) -> Result<Vec<u8>, E> {
+    let mut buf = Vec::with_capacity(value.size_hint());
+    value.ser(&mut buf)?;
+    Ok(buf)
+}
+
+impl<const N: usize, T: Readable<E> + Default + Copy, E> Readable<E> for [T; N] {
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
         T::de_array(stream)
     }
 }
-impl<const N: usize, T: Writeable<E>, E> Writeable<E> for [T; N]
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
+impl<const N: usize, T: Writeable<E>, E> Writeable<E> for [T; N] {
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
         T::ser_array(self, stream)
     }
 }
 
-impl<E> Readable<E> for String
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(s: &mut S) -> Result<Self, E> {
-        let len: i32 = s.read_i32::<LE>()?;
-        read_string(len, s)
+impl<E> Readable<E> for String {
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let len: i32 = i32::de(stream)?;
+        read_string(len, stream)
     }
 }
-impl<E> Writeable<E> for String
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
+impl<E> Writeable<E> for String {
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
         write_string(stream, self)
     }
+    fn size_hint(&self) -> usize {
+        4 + self.len() + 1
+    }
 }
-impl<E> Writeable<E> for &str
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
+impl<E> Writeable<E> for &str {
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
         write_string(stream, self)
     }
 }
 
-impl<T: Readable<E>, E> Readable<E> for Vec<T>
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E> {
-        let len = stream.read_u32::<LE>()? as usize;
+impl<T: Readable<E>, E> Readable<E> for Vec<T> {
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let len = u32::de(stream)? as usize;
         T::de_vec(len, stream)
     }
 }
-impl<T: Readable<E>, E> ReadableCtx<usize, E> for Vec<T>
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S, ctx: usize) -> Result<Self, E> {
+impl<T: Readable<E>, E> ReadableCtx<usize, E> for Vec<T> {
+    fn de<S: Reader>(stream: &mut S, ctx: usize) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
         T::de_vec(ctx, stream)
     }
 }
-impl<T: Writeable<E>, E> Writeable<E> for Vec<T>
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
-        stream.write_u32::<LE>(self.len() as u32)?;
+impl<T: Writeable<E>, E> Writeable<E> for Vec<T> {
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
+        (self.len() as u32).ser(stream)?;
         T::ser_array(self, stream)
     }
+    fn size_hint(&self) -> usize {
+        4 + self.iter().map(Writeable::size_hint).sum::<usize>()
+    }
 }
 
-impl<E> Readable<E> for bool
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E> {
-        Ok(stream.read_u32::<LE>()? != 0)
+impl<K: Readable<E> + Eq + std::hash::Hash, V: Readable<E>, E> Readable<E> for HashMap<K, V> {
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let len = u32::de(stream)? as usize;
+        <Self as ReadableCtx<usize, E>>::de(stream, len)
     }
 }
-impl<E> Writeable<E> for bool
-where
-    E: From<std::io::Error>,
+impl<K: Readable<E> + Eq + std::hash::Hash, V: Readable<E>, E> ReadableCtx<usize, E>
+    for HashMap<K, V>
 {
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
-        Ok(stream.write_u32::<LE>(if *self { 1 } else { 0 })?)
+    /// Reads `ctx` key/value pairs without an inline length prefix.
+    fn de<S: Reader>(stream: &mut S, ctx: usize) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let mut map = HashMap::with_capacity(ctx);
+        for _ in 0..ctx {
+            let key = K::de(stream)?;
+            let value = V::de(stream)?;
+            map.insert(key, value);
+        }
+        Ok(map)
     }
 }
-impl<E> Readable<E> for u8
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E> {
-        Ok(stream.read_u8()?)
+impl<K: Writeable<E>, V: Writeable<E>, E> Writeable<E> for HashMap<K, V> {
+    /// Entries are written in iteration order, which is unspecified for
+    /// `HashMap` — use `BTreeMap` if deterministic output is required.
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
+        (self.len() as u32).ser(stream)?;
+        for (k, v) in self {
+            k.ser(stream)?;
+            v.ser(stream)?;
+        }
+        Ok(())
     }
-    fn de_vec<S: Read>(len: usize, stream: &mut S) -> Result<Vec<Self>, E>
+}
+
+impl<K: Readable<E> + Ord, V: Readable<E>, E> Readable<E> for BTreeMap<K, V> {
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let len = u32::de(stream)? as usize;
+        <Self as ReadableCtx<usize, E>>::de(stream, len)
+    }
+}
+impl<K: Readable<E> + Ord, V: Readable<E>, E> ReadableCtx<usize, E> for BTreeMap<K, V> {
+    /// Reads `ctx` key/value pairs without an inline length prefix.
+    fn de<S: Reader>(stream: &mut S, ctx: usize) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let mut map = BTreeMap::new();
+        for _ in 0..ctx {
+            let key = K::de(stream)?;
+            let value = V::de(stream)?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+}
+impl<K: Writeable<E> + Ord, V: Writeable<E>, E> Writeable<E> for BTreeMap<K, V> {
+    /// Entries are written in sorted key order, so the output is
+    /// deterministic.
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
+        (self.len() as u32).ser(stream)?;
+        for (k, v) in self {
+            k.ser(stream)?;
+            v.ser(stream)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Readable<E> + Eq + std::hash::Hash, E> Readable<E> for HashSet<T> {
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let len = u32::de(stream)? as usize;
+        <Self as ReadableCtx<usize, E>>::de(stream, len)
+    }
+}
+impl<T: Readable<E> + Eq + std::hash::Hash, E> ReadableCtx<usize, E> for HashSet<T> {
+    /// Reads `ctx` elements without an inline length prefix.
+    fn de<S: Reader>(stream: &mut S, ctx: usize) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let mut set = HashSet::with_capacity(ctx);
+        for _ in 0..ctx {
+            set.insert(T::de(stream)?);
+        }
+        Ok(set)
+    }
+}
+impl<T: Writeable<E>, E> Writeable<E> for HashSet<T> {
+    /// Elements are written in iteration order, which is unspecified for
+    /// `HashSet` — use `BTreeSet` if deterministic output is required.
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
+        (self.len() as u32).ser(stream)?;
+        for v in self {
+            v.ser(stream)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Readable<E> + Ord, E> Readable<E> for BTreeSet<T> {
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let len = u32::de(stream)? as usize;
+        <Self as ReadableCtx<usize, E>>::de(stream, len)
+    }
+}
+impl<T: Readable<E> + Ord, E> ReadableCtx<usize, E> for BTreeSet<T> {
+    /// Reads `ctx` elements without an inline length prefix.
+    fn de<S: Reader>(stream: &mut S, ctx: usize) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let mut set = BTreeSet::new();
+        for _ in 0..ctx {
+            set.insert(T::de(stream)?);
+        }
+        Ok(set)
+    }
+}
+impl<T: Writeable<E> + Ord, E> Writeable<E> for BTreeSet<T> {
+    /// Elements are written in sorted order, so the output is
+    /// deterministic.
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
+        (self.len() as u32).ser(stream)?;
+        for v in self {
+            v.ser(stream)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod container_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn round_trip<T>(value: &T) -> T
+    where
+        T: Writeable<std::io::Error> + Readable<std::io::Error>,
+    {
+        let mut buf = Vec::new();
+        value.ser(&mut buf).unwrap();
+        let mut cur = Cursor::new(buf);
+        T::de(&mut cur).unwrap()
+    }
+
+    #[test]
+    fn hash_map_round_trips() {
+        let mut map = HashMap::new();
+        map.insert(1u32, "one".to_string());
+        map.insert(2u32, "two".to_string());
+        assert_eq!(round_trip(&map), map);
+    }
+
+    #[test]
+    fn btree_map_round_trips() {
+        let mut map = BTreeMap::new();
+        map.insert(1u32, "one".to_string());
+        map.insert(2u32, "two".to_string());
+        assert_eq!(round_trip(&map), map);
+    }
+
+    #[test]
+    fn hash_set_round_trips() {
+        let mut set = HashSet::new();
+        set.insert(1u32);
+        set.insert(2u32);
+        set.insert(3u32);
+        assert_eq!(round_trip(&set), set);
+    }
+
+    #[test]
+    fn btree_set_round_trips() {
+        let mut set = BTreeSet::new();
+        set.insert(1u32);
+        set.insert(2u32);
+        set.insert(3u32);
+        assert_eq!(round_trip(&set), set);
+    }
+}
+
+impl<E> Readable<E> for bool {
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        Ok(u32::de(stream)? != 0)
+    }
+}
+impl<E> Writeable<E> for bool {
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
+        (if *self { 1u32 } else { 0 }).ser(stream)
+    }
+}
+impl<E> Readable<E> for u8 {
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let mut buf = [0; 1];
+        stream.read_exact(&mut buf)?;
+        Ok(buf[0])
+    }
+    fn de_vec<S: Reader>(len: usize, stream: &mut S) -> Result<Vec<Self>, E>
     where
         Self: Sized,
+        E: From<S::Error>,
     {
         let mut buf = vec![0; len];
         stream.read_exact(&mut buf)?;
         Ok(buf)
     }
-    fn de_array<S: Read, const N: usize>(stream: &mut S) -> Result<[Self; N], E>
+    fn de_array<S: Reader, const N: usize>(stream: &mut S) -> Result<[Self; N], E>
     where
         Self: Sized + Copy + Default,
+        E: From<S::Error>,
     {
         let mut buf = [0; N];
         stream.read_exact(&mut buf)?;
         Ok(buf)
     }
 }
-impl<E> Writeable<E> for u8
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
-        Ok(stream.write_u8(*self)?)
+impl<E> Writeable<E> for u8 {
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
+        Ok(stream.write_all(std::slice::from_ref(self))?)
     }
-    fn ser_array<S: Write, T: AsRef<[Self]>>(this: T, stream: &mut S) -> Result<(), E>
+    fn ser_array<S: Writer, T: AsRef<[Self]>>(this: T, stream: &mut S) -> Result<(), E>
     where
         Self: Sized,
+        E: From<S::Error>,
     {
         Ok(stream.write_all(this.as_ref())?)
     }
 }
-impl<E> Readable<E> for i8
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E> {
-        Ok(stream.read_i8()?)
+impl<E> Readable<E> for i8 {
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        Ok(u8::de(stream)? as i8)
     }
 }
-impl<E> Writeable<E> for i8
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
-        Ok(stream.write_i8(*self)?)
+impl<E> Writeable<E> for i8 {
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
+        (*self as u8).ser(stream)
     }
 }
-impl<E> Readable<E> for u16
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E> {
-        Ok(stream.read_u16::<LE>()?)
-    }
+
+macro_rules! impl_int {
+    ($t:ty, $read:ident, $write:ident, $size:literal) => {
+        impl<E> Readable<E> for $t {
+            fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+            where
+                E: From<S::Error>,
+            {
+                let mut buf = [0; $size];
+                stream.read_exact(&mut buf)?;
+                Ok(LE::$read(&buf))
+            }
+            // Bulk path: one `read_exact` for the whole buffer instead of
+            // one syscall per element, then byte-swap in place on BE hosts
+            // (the wire format is always little-endian).
+            fn de_vec<S: Reader>(len: usize, stream: &mut S) -> Result<Vec<Self>, E>
+            where
+                Self: Sized,
+                E: From<S::Error>,
+            {
+                let mut out = vec![0 as $t; len];
+                // SAFETY: `out` is a `Vec<$t>` of `len` elements, so its
+                // allocation is at least `len * $size` bytes and aligned for
+                // `$t`; viewing it as `&mut [u8]` only narrows the alignment
+                // requirement and the byte count matches exactly, so the
+                // slice stays in bounds for the lifetime of `bytes`.
+                let bytes = unsafe {
+                    std::slice::from_raw_parts_mut(out.as_mut_ptr() as *mut u8, len * $size)
+                };
+                stream.read_exact(bytes)?;
+                #[cfg(target_endian = "big")]
+                for v in out.iter_mut() {
+                    *v = v.swap_bytes();
+                }
+                Ok(out)
+            }
+            fn de_array<S: Reader, const N: usize>(stream: &mut S) -> Result<[Self; N], E>
+            where
+                Self: Sized + Copy + Default,
+                E: From<S::Error>,
+            {
+                let mut buf = [0 as $t; N];
+                // SAFETY: `buf` is `[$t; N]`, so its backing storage is
+                // exactly `N * $size` bytes and aligned for `$t`; viewing it
+                // as `&mut [u8]` only narrows the alignment requirement and
+                // the byte count matches exactly, so the slice stays in
+                // bounds for the lifetime of `bytes`.
+                let bytes = unsafe {
+                    std::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, N * $size)
+                };
+                stream.read_exact(bytes)?;
+                #[cfg(target_endian = "big")]
+                for v in buf.iter_mut() {
+                    *v = v.swap_bytes();
+                }
+                Ok(buf)
+            }
+        }
+        impl<E> Writeable<E> for $t {
+            fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+            where
+                E: From<S::Error>,
+            {
+                let mut buf = [0; $size];
+                LE::$write(&mut buf, *self);
+                Ok(stream.write_all(&buf)?)
+            }
+            fn ser_array<S: Writer, T: AsRef<[Self]>>(this: T, stream: &mut S) -> Result<(), E>
+            where
+                Self: Sized,
+                E: From<S::Error>,
+            {
+                let slice = this.as_ref();
+                #[cfg(target_endian = "big")]
+                {
+                    let swapped: Vec<Self> = slice.iter().map(|v| v.swap_bytes()).collect();
+                    // SAFETY: `swapped` is a `Vec<$t>`, so its allocation is
+                    // exactly `swapped.len() * $size` bytes and aligned for
+                    // `$t`; viewing it as `&[u8]` only narrows the alignment
+                    // requirement and the byte count matches exactly, so the
+                    // slice stays in bounds for the lifetime of `bytes`.
+                    let bytes = unsafe {
+                        std::slice::from_raw_parts(
+                            swapped.as_ptr() as *const u8,
+                            swapped.len() * $size,
+                        )
+                    };
+                    Ok(stream.write_all(bytes)?)
+                }
+                #[cfg(target_endian = "little")]
+                {
+                    // SAFETY: `slice` borrows a `[$t]`, so its backing
+                    // storage is exactly `slice.len() * $size` bytes and
+                    // aligned for `$t`; viewing it as `&[u8]` only narrows
+                    // the alignment requirement and the byte count matches
+                    // exactly, so the slice stays in bounds for the
+                    // lifetime of `bytes`.
+                    let bytes = unsafe {
+                        std::slice::from_raw_parts(slice.as_ptr() as *const u8, slice.len() * $size)
+                    };
+                    Ok(stream.write_all(bytes)?)
+                }
+            }
+        }
+    };
 }
-impl<E> Writeable<E> for u16
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
-        Ok(stream.write_u16::<LE>(*self)?)
+impl_int!(u16, read_u16, write_u16, 2);
+impl_int!(i16, read_i16, write_i16, 2);
+impl_int!(u32, read_u32, write_u32, 4);
+impl_int!(i32, read_i32, write_i32, 4);
+impl_int!(u64, read_u64, write_u64, 8);
+impl_int!(i64, read_i64, write_i64, 8);
+
+#[cfg(test)]
+mod impl_int_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn de_vec_round_trips_bulk_read() {
+        let values: Vec<u32> = vec![0, 1, 0x7fff_ffff, 0x8000_0000, 0xffff_ffff];
+        let mut buf = Vec::new();
+        for v in &values {
+            <u32 as Writeable<std::io::Error>>::ser(v, &mut buf).unwrap();
+        }
+        let mut cur = Cursor::new(buf);
+        let out: Vec<u32> =
+            <u32 as Readable<std::io::Error>>::de_vec(values.len(), &mut cur).unwrap();
+        assert_eq!(out, values);
     }
-}
-impl<E> Readable<E> for i16
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E> {
-        Ok(stream.read_i16::<LE>()?)
+
+    #[test]
+    fn de_array_round_trips_bulk_read() {
+        let values: [i64; 4] = [0, -1, i64::MIN, i64::MAX];
+        let mut buf = Vec::new();
+        for v in &values {
+            <i64 as Writeable<std::io::Error>>::ser(v, &mut buf).unwrap();
+        }
+        let mut cur = Cursor::new(buf);
+        let out: [i64; 4] = <i64 as Readable<std::io::Error>>::de_array(&mut cur).unwrap();
+        assert_eq!(out, values);
     }
-}
-impl<E> Writeable<E> for i16
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
-        Ok(stream.write_i16::<LE>(*self)?)
+
+    #[test]
+    fn ser_array_round_trips_bulk_write() {
+        let values: Vec<u16> = vec![0, 1, 0x00ff, 0xff00, 0xffff];
+        let mut buf = Vec::new();
+        <u16 as Writeable<std::io::Error>>::ser_array(&values, &mut buf).unwrap();
+        let mut cur = Cursor::new(buf);
+        let out: Vec<u16> =
+            <u16 as Readable<std::io::Error>>::de_vec(values.len(), &mut cur).unwrap();
+        assert_eq!(out, values);
     }
 }
-impl<E> Readable<E> for u32
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E> {
-        Ok(stream.read_u32::<LE>()?)
-    }
+
+/// A `Writer` that discards every byte but tallies how many were written.
+/// Generic over `E` since `write_all` never actually fails. Backs
+/// [`serialized_size`] and [`WriteExt::ser_with_byte_prefix`].
+pub struct LengthCounter<E> {
+    pub count: usize,
+    _error: std::marker::PhantomData<fn() -> E>,
 }
-impl<E> Writeable<E> for u32
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
-        Ok(stream.write_u32::<LE>(*self)?)
+impl<E> Default for LengthCounter<E> {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            _error: std::marker::PhantomData,
+        }
     }
 }
-impl<E> Readable<E> for i32
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E> {
-        Ok(stream.read_i32::<LE>()?)
+impl<E> Writer for LengthCounter<E> {
+    type Error = E;
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.count += buf.len();
+        Ok(())
     }
 }
-impl<E> Writeable<E> for i32
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
-        Ok(stream.write_i32::<LE>(*self)?)
+
+/// Size in bytes that `value.ser(..)` would write, via [`LengthCounter`]
+pub fn serialized_size<T: Writeable<E>, E>(value: &T) -> Result<usize, E> {
+    let mut counter = LengthCounter::<E>::default();
+    value.ser(&mut counter)?;
+    Ok(counter.count)
+}
+
+#[cfg(test)]
+mod length_counter_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn serialized_size_matches_fixed_width_primitive() {
+        let size = serialized_size::<u32, std::io::Error>(&0xdead_beef).unwrap();
+        assert_eq!(size, 4);
+    }
+
+    #[test]
+    fn serialized_size_matches_length_prefixed_vec() {
+        let values: Vec<u32> = vec![1, 2, 3];
+        let size = serialized_size::<Vec<u32>, std::io::Error>(&values).unwrap();
+        assert_eq!(size, 4 + 3 * 4);
+    }
+
+    #[test]
+    fn serialized_size_never_fails_on_an_infallible_error() {
+        let size = serialized_size::<u32, std::convert::Infallible>(&7).unwrap();
+        assert_eq!(size, 4);
+    }
+
+    #[test]
+    fn ser_with_byte_prefix_round_trips_with_its_own_length() {
+        let values: Vec<u32> = vec![10, 20, 30];
+        let mut buf = Vec::new();
+        WriteExt::ser_with_byte_prefix::<_, std::io::Error>(&mut buf, &values).unwrap();
+
+        let mut cur = Cursor::new(buf);
+        let prefix = <u32 as Readable<std::io::Error>>::de(&mut cur).unwrap();
+        assert_eq!(
+            prefix as usize,
+            serialized_size::<Vec<u32>, std::io::Error>(&values).unwrap()
+        );
+        let decoded = <Vec<u32> as Readable<std::io::Error>>::de(&mut cur).unwrap();
+        assert_eq!(decoded, values);
     }
 }
-impl<E> Readable<E> for u64
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E> {
-        Ok(stream.read_u64::<LE>()?)
+
+/// 128-bit globally unique identifier, as used by Unreal's `FGuid`.
+pub type Guid = [u8; 16];
+
+/// Version info threaded through (de)serialization, mirroring Unreal's
+/// `FArchive`, so fields can be gated on object/custom version.
+#[derive(Debug, Default, Clone)]
+pub struct Archive {
+    pub object_version: u32,
+    pub licensee_version: Option<u32>,
+    pub custom_versions: HashMap<Guid, i32>,
+}
+impl Archive {
+    pub fn new(object_version: u32) -> Self {
+        Self {
+            object_version,
+            licensee_version: None,
+            custom_versions: HashMap::new(),
+        }
+    }
+    /// Version registered for `guid`, or `-1` if absent.
+    pub fn custom_version(&self, guid: Guid) -> i32 {
+        self.custom_versions.get(&guid).copied().unwrap_or(-1)
+    }
+    pub fn set_custom_version(&mut self, guid: Guid, version: i32) {
+        self.custom_versions.insert(guid, version);
     }
 }
-impl<E> Writeable<E> for u64
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
-        Ok(stream.write_u64::<LE>(*self)?)
+
+/// Reads `T` only when `archive`'s version for `guid` is >= `min_version`,
+/// otherwise `None` without touching the stream.
+impl<T: Readable<E>, E> ReadableCtx<(&Archive, Guid, i32), E> for Option<T> {
+    fn de<S: Reader>(
+        stream: &mut S,
+        (archive, guid, min_version): (&Archive, Guid, i32),
+    ) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        if archive.custom_version(guid) >= min_version {
+            Ok(Some(T::de(stream)?))
+        } else {
+            Ok(None)
+        }
     }
 }
-impl<E> Readable<E> for i64
-where
-    E: From<std::io::Error>,
-{
-    fn de<S: Read>(stream: &mut S) -> Result<Self, E> {
-        Ok(stream.read_i64::<LE>()?)
+impl<T: Writeable<E>, E> WriteableCtx<(&Archive, Guid, i32), E> for Option<T> {
+    fn ser<S: Writer>(
+        &self,
+        stream: &mut S,
+        (archive, guid, min_version): (&Archive, Guid, i32),
+    ) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
+        if archive.custom_version(guid) >= min_version {
+            if let Some(value) = self {
+                value.ser(stream)?;
+            }
+        }
+        Ok(())
     }
 }
-impl<E> Writeable<E> for i64
-where
-    E: From<std::io::Error>,
-{
-    fn ser<S: Write>(&self, stream: &mut S) -> Result<(), E> {
-        Ok(stream.write_i64::<LE>(*self)?)
+
+#[cfg(test)]
+mod archive_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const FEATURE_GUID: Guid = [1; 16];
+
+    #[test]
+    fn custom_version_defaults_to_invalid() {
+        let archive = Archive::new(0);
+        assert_eq!(archive.custom_version(FEATURE_GUID), -1);
+    }
+
+    #[test]
+    fn option_ctx_reads_field_when_version_gate_is_met() {
+        let mut archive = Archive::new(0);
+        archive.set_custom_version(FEATURE_GUID, 3);
+        let mut buf = Vec::new();
+        <u32 as Writeable<std::io::Error>>::ser(&42u32, &mut buf).unwrap();
+        let mut cur = Cursor::new(buf);
+        let field: Option<u32> = <Option<u32> as ReadableCtx<_, std::io::Error>>::de(
+            &mut cur,
+            (&archive, FEATURE_GUID, 3),
+        )
+        .unwrap();
+        assert_eq!(field, Some(42));
+    }
+
+    #[test]
+    fn option_ctx_skips_field_without_touching_stream_when_version_gate_fails() {
+        let mut archive = Archive::new(0);
+        archive.set_custom_version(FEATURE_GUID, 2);
+        let mut cur = Cursor::new(Vec::<u8>::new());
+        let field: Option<u32> = <Option<u32> as ReadableCtx<_, std::io::Error>>::de(
+            &mut cur,
+            (&archive, FEATURE_GUID, 3),
+        )
+        .unwrap();
+        assert_eq!(field, None);
+    }
+
+    #[test]
+    fn option_ctx_round_trips_through_ser_and_de() {
+        let mut archive = Archive::new(0);
+        archive.set_custom_version(FEATURE_GUID, 5);
+        let value: Option<u32> = Some(7);
+        let mut buf = Vec::new();
+        <Option<u32> as WriteableCtx<_, std::io::Error>>::ser(
+            &value,
+            &mut buf,
+            (&archive, FEATURE_GUID, 3),
+        )
+        .unwrap();
+        let mut cur = Cursor::new(buf);
+        let decoded: Option<u32> = <Option<u32> as ReadableCtx<_, std::io::Error>>::de(
+            &mut cur,
+            (&archive, FEATURE_GUID, 3),
+        )
+        .unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn option_ctx_writes_nothing_when_version_gate_fails() {
+        let mut archive = Archive::new(0);
+        archive.set_custom_version(FEATURE_GUID, 2);
+        let value: Option<u32> = Some(7);
+        let mut buf = Vec::new();
+        <Option<u32> as WriteableCtx<_, std::io::Error>>::ser(
+            &value,
+            &mut buf,
+            (&archive, FEATURE_GUID, 3),
+        )
+        .unwrap();
+        assert!(buf.is_empty());
     }
 }
 
-pub fn read_array<S: Read, T, F, E>(len: usize, stream: &mut S, mut f: F) -> Result<Vec<T>, E>
+pub fn read_array<S: Reader, T, F, E>(len: usize, stream: &mut S, mut f: F) -> Result<Vec<T>, E>
 where
     F: FnMut(&mut S) -> Result<T, E>,
-    E: From<std::io::Error>,
+    E: From<S::Error>,
 {
     let mut array = Vec::with_capacity(len);
     for _ in 0..len {
@@ -335,12 +956,12 @@ where
     Ok(array)
 }
 
-pub fn read_string<S: Read, E>(len: i32, stream: &mut S) -> Result<String, E>
+pub fn read_string<S: Reader, E>(len: i32, stream: &mut S) -> Result<String, E>
 where
-    E: From<std::io::Error>,
+    E: From<S::Error>,
 {
     if len < 0 {
-        let chars = read_array((-len) as usize, stream, |r| r.read_u16::<LE>())?;
+        let chars = read_array((-len) as usize, stream, |r| u16::de(r))?;
         let length = chars.iter().position(|&c| c == 0).unwrap_or(chars.len());
         Ok(String::from_utf16(&chars[..length]).unwrap())
     } else {
@@ -351,23 +972,182 @@ where
     }
 }
 
-pub fn write_string<S: Write, E>(stream: &mut S, value: &str) -> Result<(), E>
+pub fn write_string<S: Writer, E>(stream: &mut S, value: &str) -> Result<(), E>
 where
-    E: From<std::io::Error>,
+    E: From<S::Error>,
 {
     if value.is_empty() {
-        stream.write_u32::<LE>(0)?;
+        0u32.ser(stream)?;
     } else if value.is_ascii() {
-        stream.write_u32::<LE>(value.len() as u32 + 1)?;
+        (value.len() as u32 + 1).ser(stream)?;
         stream.write_all(value.as_bytes())?;
-        stream.write_u8(0)?;
+        0u8.ser(stream)?;
     } else {
         let chars: Vec<u16> = value.encode_utf16().collect();
-        stream.write_i32::<LE>(-(chars.len() as i32 + 1))?;
+        (-(chars.len() as i32 + 1)).ser(stream)?;
         for c in chars {
-            stream.write_u16::<LE>(c)?;
+            c.ser(stream)?;
         }
-        stream.write_u16::<LE>(0)?;
+        0u16.ser(stream)?;
     }
     Ok(())
 }
+
+/// Max encoded length of a [`VarInt`]/[`ZigZagVarInt`]; 10 bytes cover every
+/// 64-bit value.
+const VARINT_MAX_BYTES: usize = 10;
+
+/// Returned when a [`VarInt`] runs past [`VARINT_MAX_BYTES`] without terminating
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VarIntTooLong;
+impl std::fmt::Display for VarIntTooLong {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "varint exceeded {VARINT_MAX_BYTES} bytes")
+    }
+}
+impl std::error::Error for VarIntTooLong {}
+impl From<VarIntTooLong> for std::io::Error {
+    fn from(e: VarIntTooLong) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// Variable-length integer: 7 payload bits per byte, high bit set if
+/// another byte follows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VarInt(pub u64);
+impl<E> Readable<E> for VarInt
+where
+    E: From<VarIntTooLong>,
+{
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let mut value = 0u64;
+        for i in 0..VARINT_MAX_BYTES {
+            let byte = <u8 as Readable<E>>::de(stream)?;
+            value |= ((byte & 0x7f) as u64) << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok(VarInt(value));
+            }
+        }
+        Err(VarIntTooLong.into())
+    }
+}
+impl<E> Writeable<E> for VarInt {
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
+        let mut value = self.0;
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                byte.ser(stream)?;
+                return Ok(());
+            }
+            (byte | 0x80).ser(stream)?;
+        }
+    }
+}
+
+/// Signed [`VarInt`] via zig-zag encoding, so small negative values stay compact
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ZigZagVarInt(pub i64);
+impl ZigZagVarInt {
+    fn zigzag_encode(n: i64) -> u64 {
+        ((n << 1) ^ (n >> 63)) as u64
+    }
+    fn zigzag_decode(z: u64) -> i64 {
+        ((z >> 1) as i64) ^ -((z & 1) as i64)
+    }
+}
+impl<E> Readable<E> for ZigZagVarInt
+where
+    E: From<VarIntTooLong>,
+{
+    fn de<S: Reader>(stream: &mut S) -> Result<Self, E>
+    where
+        E: From<S::Error>,
+    {
+        let VarInt(z) = <VarInt as Readable<E>>::de(stream)?;
+        Ok(ZigZagVarInt(Self::zigzag_decode(z)))
+    }
+}
+impl<E> Writeable<E> for ZigZagVarInt {
+    fn ser<S: Writer>(&self, stream: &mut S) -> Result<(), E>
+    where
+        E: From<S::Error>,
+    {
+        VarInt(Self::zigzag_encode(self.0)).ser(stream)
+    }
+}
+
+/// Reads a `Vec<T>` whose length is a [`VarInt`] rather than a fixed `u32`
+pub fn de_vec_varint<S: Reader, T: Readable<E>, E>(stream: &mut S) -> Result<Vec<T>, E>
+where
+    E: From<S::Error> + From<VarIntTooLong>,
+{
+    let VarInt(len) = <VarInt as Readable<E>>::de(stream)?;
+    T::de_vec(len as usize, stream)
+}
+
+/// Writes `value` with its length as a [`VarInt`]. Pairs with [`de_vec_varint`]
+pub fn ser_vec_varint<S: Writer, T: Writeable<E>, E>(value: &[T], stream: &mut S) -> Result<(), E>
+where
+    E: From<S::Error>,
+{
+    VarInt(value.len() as u64).ser(stream)?;
+    T::ser_array(value, stream)
+}
+
+#[cfg(test)]
+mod varint_tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn var_int_round_trips_across_byte_boundaries() {
+        for value in [0u64, 1, 127, 128, 16383, 16384, u32::MAX as u64, u64::MAX] {
+            let mut buf = Vec::new();
+            <VarInt as Writeable<std::io::Error>>::ser(&VarInt(value), &mut buf).unwrap();
+            let mut cur = Cursor::new(buf);
+            let decoded = <VarInt as Readable<std::io::Error>>::de(&mut cur).unwrap();
+            assert_eq!(decoded, VarInt(value));
+        }
+    }
+
+    #[test]
+    fn zig_zag_var_int_round_trips_negative_and_positive() {
+        for value in [0i64, -1, 1, -2, 2, i64::MIN, i64::MAX] {
+            let mut buf = Vec::new();
+            <ZigZagVarInt as Writeable<std::io::Error>>::ser(&ZigZagVarInt(value), &mut buf)
+                .unwrap();
+            let mut cur = Cursor::new(buf);
+            let decoded = <ZigZagVarInt as Readable<std::io::Error>>::de(&mut cur).unwrap();
+            assert_eq!(decoded, ZigZagVarInt(value));
+        }
+    }
+
+    #[test]
+    fn var_int_rejects_encoding_past_max_bytes() {
+        // Every byte has its continuation bit set and none terminate the
+        // encoding, simulating a malicious/corrupt stream.
+        let malicious = vec![0x80u8; VARINT_MAX_BYTES + 1];
+        let mut cur = Cursor::new(malicious);
+        let err = <VarInt as Readable<std::io::Error>>::de(&mut cur).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn vec_varint_round_trips_with_compact_length_prefix() {
+        let values: Vec<u32> = vec![10, 20, 30, 40];
+        let mut buf = Vec::new();
+        ser_vec_varint::<_, u32, std::io::Error>(&values, &mut buf).unwrap();
+        let mut cur = Cursor::new(buf);
+        let decoded = de_vec_varint::<_, u32, std::io::Error>(&mut cur).unwrap();
+        assert_eq!(decoded, values);
+    }
+}